@@ -0,0 +1,181 @@
+// Конфигурация прокси: список Geyser endpoint'ов, commitment level, наблюдаемые
+// программы, TCP bind address и ёмкость broadcast канала. Грузится либо из TOML
+// файла (путь в `GRPC_PROXY_CONFIG_FILE`), либо из отдельных переменных окружения.
+
+use serde::Deserialize;
+use yellowstone_grpc_proto::prelude::CommitmentLevel;
+
+use crate::grpc_client::GrpcSourceConfig;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitmentLevelConfig {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl CommitmentLevelConfig {
+    pub fn to_proto(self) -> CommitmentLevel {
+        match self {
+            CommitmentLevelConfig::Processed => CommitmentLevel::Processed,
+            CommitmentLevelConfig::Confirmed => CommitmentLevel::Confirmed,
+            CommitmentLevelConfig::Finalized => CommitmentLevel::Finalized,
+        }
+    }
+
+    fn from_env_str(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "processed" => Some(CommitmentLevelConfig::Processed),
+            "confirmed" => Some(CommitmentLevelConfig::Confirmed),
+            "finalized" => Some(CommitmentLevelConfig::Finalized),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppConfig {
+    pub sources: Vec<GrpcSourceConfig>,
+    pub commitment_level: CommitmentLevelConfig,
+    pub program_ids: Vec<String>,
+    pub tcp_bind_addr: String,
+    pub broadcast_capacity: usize,
+}
+
+const DEFAULT_PUMP_FUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+const DEFAULT_TCP_BIND_ADDR: &str = "0.0.0.0:8725";
+const DEFAULT_BROADCAST_CAPACITY: usize = 10_000;
+
+/// Загружает конфиг из `GRPC_PROXY_CONFIG_FILE` (TOML), либо собирает его из
+/// отдельных переменных окружения, либо (для локальной разработки) из дефолтов,
+/// совпадающих с прежним хардкодом.
+pub fn load() -> anyhow::Result<AppConfig> {
+    if let Ok(path) = std::env::var("GRPC_PROXY_CONFIG_FILE") {
+        let raw = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("failed to read config file {}: {}", path, e))?;
+        let config: AppConfig = toml::from_str(&raw)
+            .map_err(|e| anyhow::anyhow!("failed to parse config file {}: {}", path, e))?;
+        return Ok(config);
+    }
+
+    Ok(AppConfig {
+        sources: sources_from_env(),
+        commitment_level: std::env::var("GRPC_PROXY_COMMITMENT")
+            .ok()
+            .and_then(|v| CommitmentLevelConfig::from_env_str(&v))
+            .unwrap_or(CommitmentLevelConfig::Processed),
+        program_ids: std::env::var("GRPC_PROXY_PROGRAM_IDS")
+            .ok()
+            .map(|v| v.split(',').map(str::to_string).collect())
+            .unwrap_or_else(|| vec![DEFAULT_PUMP_FUN_PROGRAM_ID.to_string()]),
+        tcp_bind_addr: std::env::var("GRPC_PROXY_TCP_ADDR")
+            .unwrap_or_else(|_| DEFAULT_TCP_BIND_ADDR.to_string()),
+        broadcast_capacity: std::env::var("GRPC_PROXY_BROADCAST_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_BROADCAST_CAPACITY),
+    })
+}
+
+/// Формат `GRPC_PROXY_ENDPOINTS`: `name=url[|x_token[|tls]];name2=url2[|x_token2[|tls2]]`,
+/// где `tls` - `true`/`false` (по умолчанию `true`), так что список может мешать
+/// https и plaintext endpoint'ы.
+fn sources_from_env() -> Vec<GrpcSourceConfig> {
+    let Ok(raw) = std::env::var("GRPC_PROXY_ENDPOINTS") else {
+        return default_sources();
+    };
+
+    parse_endpoints(&raw)
+}
+
+fn default_sources() -> Vec<GrpcSourceConfig> {
+    vec![GrpcSourceConfig {
+        name: "gadflynode".to_string(),
+        endpoint: "https://fr.grpc.gadflynode.com:25565".to_string(),
+        x_token: None,
+        tls: true,
+    }]
+}
+
+/// Parses the `GRPC_PROXY_ENDPOINTS` format into sources. Pulled out of
+/// `sources_from_env` so the string-parsing logic can be unit tested without
+/// touching process env vars. Note: an empty/unset-but-present string (and any
+/// entry that fails to parse) silently yields zero/fewer sources - there's no
+/// separate empty-list warning, so a typo'd env var just looks like "no sources".
+fn parse_endpoints(raw: &str) -> Vec<GrpcSourceConfig> {
+    raw.split(';')
+        .filter(|entry| !entry.is_empty())
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            let (name, rest) = match entry.split_once('=') {
+                Some((name, rest)) => (name.to_string(), rest),
+                None => (format!("source-{i}"), entry),
+            };
+            let mut parts = rest.split('|');
+            let endpoint = parts.next()?;
+            let x_token = parts.next().filter(|s| !s.is_empty()).map(str::to_string);
+            let tls = parts.next().map(|s| s != "false").unwrap_or(true);
+            Some(GrpcSourceConfig {
+                name,
+                endpoint: endpoint.to_string(),
+                x_token,
+                tls,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_env_str_accepts_known_levels_case_insensitively() {
+        assert_eq!(CommitmentLevelConfig::from_env_str("Processed"), Some(CommitmentLevelConfig::Processed));
+        assert_eq!(CommitmentLevelConfig::from_env_str("confirmed"), Some(CommitmentLevelConfig::Confirmed));
+        assert_eq!(CommitmentLevelConfig::from_env_str("FINALIZED"), Some(CommitmentLevelConfig::Finalized));
+    }
+
+    #[test]
+    fn from_env_str_rejects_unknown_level() {
+        assert_eq!(CommitmentLevelConfig::from_env_str("archived"), None);
+    }
+
+    #[test]
+    fn parse_endpoints_handles_multiple_entries_with_token_and_tls() {
+        let sources = parse_endpoints("a=https://a.example|token-a|false;b=https://b.example|token-b");
+
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].name, "a");
+        assert_eq!(sources[0].endpoint, "https://a.example");
+        assert_eq!(sources[0].x_token.as_deref(), Some("token-a"));
+        assert!(!sources[0].tls);
+
+        assert_eq!(sources[1].name, "b");
+        assert_eq!(sources[1].x_token.as_deref(), Some("token-b"));
+        assert!(sources[1].tls);
+    }
+
+    #[test]
+    fn parse_endpoints_defaults_tls_true_and_no_token() {
+        let sources = parse_endpoints("solo=https://solo.example");
+
+        assert_eq!(sources.len(), 1);
+        assert!(sources[0].x_token.is_none());
+        assert!(sources[0].tls);
+    }
+
+    #[test]
+    fn parse_endpoints_falls_back_to_positional_name() {
+        let sources = parse_endpoints("https://no-name.example");
+
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].name, "source-0");
+    }
+
+    #[test]
+    fn parse_endpoints_on_empty_string_yields_no_sources() {
+        assert!(parse_endpoints("").is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+// Клиентские фильтры подписки, передаваемые перед стримом через TCP хендшейк
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tracing::warn;
+
+use crate::CreateTransaction;
+
+/// Сколько ждем хендшейк-фрейм, прежде чем решить, что клиент его не пришлет
+/// (старые клиенты просто начинают читать стрим, ничего не отправляя).
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Верхняя граница на длину хендшейк-фрейма: реальный payload - это несколько
+/// строк и булей, пара KB с большим запасом. Защищает от клиента, присылающего
+/// announced-length 0xFFFFFFFF и вынуждающего аллоцировать под него буфер.
+const MAX_FILTER_FRAME_LEN: usize = 4096;
+
+/// Предикат подписки, который клиент присылает одним bincode-фреймом перед стримом.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionFilter {
+    /// Пусто = не фильтруем по создателю.
+    pub creator_allowlist: Vec<String>,
+    /// Пусто/None = не фильтруем по суффиксу минта.
+    pub mint_suffix: Option<String>,
+    /// false = отбрасываем CreateV2, оставляя только Create.
+    pub include_create_v2: bool,
+    /// false = отбрасываем Create (v1), оставляя только CreateV2.
+    pub include_create_v1: bool,
+}
+
+impl Default for SubscriptionFilter {
+    /// "Пусто" значит "подписка на всё", а не "на ничего" - не наследуем
+    /// derive(Default), потому что он дал бы `include_create_v1/v2 = false`.
+    fn default() -> Self {
+        Self::match_all()
+    }
+}
+
+impl SubscriptionFilter {
+    /// Фильтр "пропускать всё" - используется, когда клиент не прислал хендшейк
+    /// (и как единственная реализация [`Default`]).
+    fn match_all() -> Self {
+        Self {
+            creator_allowlist: Vec::new(),
+            mint_suffix: None,
+            include_create_v2: true,
+            include_create_v1: true,
+        }
+    }
+
+    pub fn matches(&self, tx: &CreateTransaction) -> bool {
+        if tx.is_create_v2 && !self.include_create_v2 {
+            return false;
+        }
+        if !tx.is_create_v2 && !self.include_create_v1 {
+            return false;
+        }
+
+        if !self.creator_allowlist.is_empty()
+            && !self.creator_allowlist.iter().any(|c| c == &tx.creator_address)
+        {
+            return false;
+        }
+
+        if let Some(suffix) = &self.mint_suffix {
+            if !tx.mint_address.ends_with(suffix.as_str()) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Читает опциональный хендшейк-фрейм (4-байтная LE длина + bincode payload),
+/// таким же образом, каким сервер отправляет данные. Длина 0 или отсутствие
+/// фрейма в течение [`HANDSHAKE_TIMEOUT`] трактуются как "подписка на всё".
+pub async fn read_subscription_filter(stream: &mut tokio::net::TcpStream) -> SubscriptionFilter {
+    match tokio::time::timeout(HANDSHAKE_TIMEOUT, read_filter_frame(stream)).await {
+        Ok(Ok(Some(filter))) => filter,
+        Ok(Ok(None)) => SubscriptionFilter::match_all(),
+        Ok(Err(e)) => {
+            warn!("Failed to read subscription filter handshake, defaulting to match-all: {}", e);
+            SubscriptionFilter::match_all()
+        }
+        Err(_) => SubscriptionFilter::match_all(),
+    }
+}
+
+async fn read_filter_frame(stream: &mut tokio::net::TcpStream) -> anyhow::Result<Option<SubscriptionFilter>> {
+    let mut len_bytes = [0u8; 4];
+    stream.read_exact(&mut len_bytes).await?;
+    let len = u32::from_le_bytes(len_bytes) as usize;
+
+    if len == 0 {
+        return Ok(None);
+    }
+    if len > MAX_FILTER_FRAME_LEN {
+        anyhow::bail!("handshake frame length {len} exceeds max {MAX_FILTER_FRAME_LEN}");
+    }
+
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).await?;
+    let filter: SubscriptionFilter = bincode::deserialize(&payload)?;
+    Ok(Some(filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tx(creator: &str, mint: &str, is_create_v2: bool) -> CreateTransaction {
+        CreateTransaction {
+            signature: "sig".to_string(),
+            mint_address: mint.to_string(),
+            creator_address: creator.to_string(),
+            bonding_curve_address: "curve".to_string(),
+            name: "Name".to_string(),
+            symbol: "SYM".to_string(),
+            uri: "uri".to_string(),
+            slot: 1,
+            is_create_v2,
+        }
+    }
+
+    #[test]
+    fn default_and_match_all_accept_everything() {
+        let tx = sample_tx("creator-a", "mintpump", false);
+        assert!(SubscriptionFilter::default().matches(&tx));
+        assert!(SubscriptionFilter::match_all().matches(&tx));
+    }
+
+    #[test]
+    fn creator_allowlist_rejects_other_creators() {
+        let filter = SubscriptionFilter {
+            creator_allowlist: vec!["creator-a".to_string()],
+            ..SubscriptionFilter::match_all()
+        };
+
+        assert!(filter.matches(&sample_tx("creator-a", "mint", false)));
+        assert!(!filter.matches(&sample_tx("creator-b", "mint", false)));
+    }
+
+    #[test]
+    fn mint_suffix_filters_by_ending() {
+        let filter = SubscriptionFilter {
+            mint_suffix: Some("pump".to_string()),
+            ..SubscriptionFilter::match_all()
+        };
+
+        assert!(filter.matches(&sample_tx("creator", "xyzpump", false)));
+        assert!(!filter.matches(&sample_tx("creator", "xyzmoon", false)));
+    }
+
+    #[test]
+    fn version_flags_exclude_the_other_variant() {
+        let only_v2 = SubscriptionFilter {
+            include_create_v1: false,
+            ..SubscriptionFilter::match_all()
+        };
+        assert!(only_v2.matches(&sample_tx("c", "m", true)));
+        assert!(!only_v2.matches(&sample_tx("c", "m", false)));
+
+        let only_v1 = SubscriptionFilter {
+            include_create_v2: false,
+            ..SubscriptionFilter::match_all()
+        };
+        assert!(only_v1.matches(&sample_tx("c", "m", false)));
+        assert!(!only_v1.matches(&sample_tx("c", "m", true)));
+    }
+}
@@ -3,56 +3,266 @@
 use yellowstone_grpc_client::{GeyserGrpcClient, ClientTlsConfig};
 use yellowstone_grpc_proto::prelude::*;
 use bs58;
-use tracing::{info, error};
+use borsh::BorshDeserialize;
+use tracing::{info, warn, error};
 use std::time::Duration;
-use futures::{SinkExt, StreamExt};
-use std::collections::HashMap;
+use std::pin::Pin;
+use futures::{SinkExt, Stream, StreamExt};
+use futures::stream::{self, BoxStream};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::error::Error;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::UnboundedReceiverStream;
 
+use crate::config::AppConfig;
+use crate::metrics;
 use crate::AppState;
 use crate::CreateTransaction;
 use std::sync::Arc;
 
+/// Распарсенная транзакция вместе с моментом, когда она была замечена в GRPC
+/// стриме - нужен, чтобы посчитать end-to-end задержку до broadcast.
+struct ParsedEvent {
+    tx: CreateTransaction,
+    observed_at: Instant,
+}
+
+/// Описывает один Geyser endpoint, к которому можно подключиться независимо от остальных.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct GrpcSourceConfig {
+    pub name: String,
+    pub endpoint: String,
+    pub x_token: Option<String>,
+    /// Подключаться ли по TLS. По умолчанию true, т.к. большинство Geyser
+    /// endpoint'ов отдаются по https - ставим false для plaintext/локальных.
+    #[serde(default = "default_tls")]
+    pub tls: bool,
+}
+
+fn default_tls() -> bool {
+    true
+}
+
+/// Сколько последних подписей храним, чтобы отбрасывать дубликаты, прилетевшие
+/// с нескольких источников одновременно.
+const SEEN_CACHE_CAPACITY: usize = 10_000;
+
+/// Простой bounded FIFO-кэш по подписям: HashSet для O(1) проверки + VecDeque для порядка
+/// вытеснения. Вытесняется самая давно вставленная подпись; повторная встреча подписи
+/// её позицию в очереди не обновляет.
+struct SeenCache {
+    capacity: usize,
+    order: VecDeque<String>,
+    set: HashSet<String>,
+}
+
+impl SeenCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            set: HashSet::with_capacity(capacity),
+        }
+    }
+
+    /// Возвращает true, если подпись видим впервые (и в этом случае запоминает её).
+    fn insert_if_new(&mut self, signature: &str) -> bool {
+        if !self.set.insert(signature.to_string()) {
+            return false;
+        }
+
+        self.order.push_back(signature.to_string());
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+
+        true
+    }
+}
+
 pub async fn run_grpc_subscription(state: Arc<AppState>) -> anyhow::Result<()> {
-    let endpoint = "https://fr.grpc.gadflynode.com:25565";
-    let token = ""; // Gadflynode public endpoint doesn't require token
+    let config = state.config.clone();
+
+    // Каждый источник пишет распарсенные транзакции в свой собственный канал и
+    // переподключается независимо от остальных, так что зависший/упавший
+    // Geyser-узел не останавливает доставку с других узлов.
+    let mut per_source_streams: Vec<BoxStream<'static, ParsedEvent>> = Vec::new();
+    for source in config.sources.clone() {
+        let (tx, rx) = mpsc::unbounded_channel::<ParsedEvent>();
+        tokio::spawn(run_source_with_backoff(source, config.clone(), tx));
+        per_source_streams.push(UnboundedReceiverStream::new(rx).boxed());
+    }
+
+    // futures::stream::select_all опрашивает все источники по очереди, так что ни
+    // один из них не может монополизировать доставку.
+    let mut merged = stream::select_all(per_source_streams);
+    let mut seen = SeenCache::new(SEEN_CACHE_CAPACITY);
+
+    while let Some(event) = merged.next().await {
+        if !seen.insert_if_new(&event.tx.signature) {
+            continue;
+        }
+
+        metrics::END_TO_END_LAG_SECONDS.observe(event.observed_at.elapsed().as_secs_f64());
+        metrics::CREATE_TRANSACTIONS_TOTAL.inc();
+
+        info!("🔥 Create detected: mint={} creator={} signature={}",
+            event.tx.mint_address, event.tx.creator_address, event.tx.signature);
+
+        if state.tx_sender.send(event.tx).is_err() {
+            metrics::CREATE_TRANSACTIONS_DROPPED_NO_SUBSCRIBERS.inc();
+            warn!("No TCP subscribers, dropping Create transaction");
+        }
+    }
+
+    Ok(())
+}
+
+/// Raw-стрим апдейтов Yellowstone, уже привязанный к отправленной подписке.
+type UpdatesStream = Pin<Box<dyn Stream<Item = Result<SubscribeUpdate, tonic::Status>> + Send>>;
+
+/// Sink запросов той же bidi-подписки. `Subscribe` - bidirectional streaming RPC:
+/// если уронить эту половину, запрос-стрим полу-закрывается и Geyser-сервер
+/// обычно сразу рвет подписку, так что держим её живой рядом с `updates_stream`.
+type RequestSink = Pin<Box<dyn futures::Sink<SubscribeRequest, Error = anyhow::Error> + Send>>;
+
+/// Явный автомат состояний подключения к одному источнику. Ключевой инвариант
+/// (из geyser-grpc-connector): счетчик попыток сбрасывается только когда стрим
+/// реально дошел до `Ready` и отдал первое сообщение - не когда таск всего лишь
+/// вернулся без ошибки, потому что в штатном режиме он вообще никогда не возвращается.
+enum ConnectionState {
+    NotConnected(u32),
+    Connecting(u32, JoinHandle<anyhow::Result<(RequestSink, UpdatesStream)>>),
+    Ready(u32, RequestSink, UpdatesStream, bool),
+    WaitReconnect(u32),
+}
+
+fn backoff_for_attempt(attempt: u32) -> Duration {
+    let base = Duration::from_secs(1);
+    std::cmp::min(base * 2u32.saturating_pow(attempt), Duration::from_secs(30))
+}
+
+/// Ключевой инвариант `ConnectionState::Ready`: счетчик попыток сбрасывается в 0
+/// ровно один раз за сессию - при первом сообщении после подключения - а не на
+/// каждое последующее. Возвращает `(attempt после этого сообщения, has_reset)`.
+fn reset_attempt(attempt: u32, has_reset: bool) -> (u32, bool) {
+    if has_reset {
+        (attempt, has_reset)
+    } else {
+        (0, true)
+    }
+}
+
+/// Управляет подключением к одному источнику, переподключаясь с собственным backoff.
+async fn run_source_with_backoff(
+    source: GrpcSourceConfig,
+    config: Arc<AppConfig>,
+    tx: mpsc::UnboundedSender<ParsedEvent>,
+) {
+    let mut state = ConnectionState::NotConnected(0);
 
-    let mut backoff_interval = Duration::from_secs(1);
     loop {
-        match subscribe_once(endpoint, token, state.clone()).await {
-            Ok(_) => {
-                // Успешное подключение - сбрасываем backoff
-                backoff_interval = Duration::from_secs(1);
+        if tx.is_closed() {
+            // Мерж-стрим больше никто не слушает, нет смысла переподключаться.
+            return;
+        }
+
+        state = match state {
+            ConnectionState::NotConnected(attempt) => {
+                if attempt > 0 {
+                    metrics::GRPC_RECONNECTS_TOTAL.with_label_values(&[&source.name]).inc();
+                }
+                info!("[{}] Connecting to GRPC endpoint: {}", source.name, source.endpoint);
+                let source = source.clone();
+                let config = config.clone();
+                let handle = tokio::spawn(async move { connect_and_subscribe(&source, &config).await });
+                ConnectionState::Connecting(attempt, handle)
             }
-            Err(e) => {
-                error!("GRPC error: {} (will reconnect in {:?})", e, backoff_interval);
-                tokio::time::sleep(backoff_interval).await;
-                // Увеличиваем задержку экспоненциально, максимум 30 секунд
-                backoff_interval = std::cmp::min(backoff_interval * 2, Duration::from_secs(30));
+            ConnectionState::Connecting(attempt, handle) => {
+                match handle.await {
+                    Ok(Ok((subscribe_tx, updates_stream))) => {
+                        info!("[{}] ✅ Successfully subscribed, listening for Create transactions...", source.name);
+                        ConnectionState::Ready(attempt, subscribe_tx, updates_stream, false)
+                    }
+                    Ok(Err(e)) => {
+                        error!("[{}] GRPC connect error: {} (will reconnect)", source.name, e);
+                        ConnectionState::WaitReconnect(attempt + 1)
+                    }
+                    Err(join_err) => {
+                        error!("[{}] Connect task panicked: {}", source.name, join_err);
+                        ConnectionState::WaitReconnect(attempt + 1)
+                    }
+                }
             }
-        }
+            ConnectionState::Ready(attempt, subscribe_tx, mut updates_stream, has_reset) => {
+                match updates_stream.next().await {
+                    Some(Ok(message)) => {
+                        // Первое сообщение после подключения доказывает, что стрим живой -
+                        // сбрасываем счетчик попыток ровно один раз за сессию.
+                        let (attempt, has_reset) = reset_attempt(attempt, has_reset);
+
+                        if let Some(update) = message.update_oneof {
+                            if let UpdateOneof::Transaction(update_tx) = update {
+                                if let Some(parsed) = parse_create_transaction(&update_tx, &config.program_ids) {
+                                    let event = ParsedEvent { tx: parsed, observed_at: Instant::now() };
+                                    if tx.send(event).is_err() {
+                                        return;
+                                    }
+                                }
+                            }
+                        }
+
+                        ConnectionState::Ready(attempt, subscribe_tx, updates_stream, has_reset)
+                    }
+                    Some(Err(e)) => {
+                        error!("[{}] GRPC stream error: {} (code: {:?}, message: {})",
+                            source.name, e, e.code(), e.message());
+                        metrics::GRPC_STREAM_ERRORS_TOTAL
+                            .with_label_values(&[&source.name, &format!("{:?}", e.code())])
+                            .inc();
+                        if let Some(source_err) = Error::source(&e) {
+                            error!("[{}] Source error: {}", source.name, source_err);
+                        }
+                        ConnectionState::WaitReconnect(attempt + 1)
+                    }
+                    None => {
+                        error!("[{}] GRPC stream closed unexpectedly (server closed connection)", source.name);
+                        ConnectionState::WaitReconnect(attempt + 1)
+                    }
+                }
+            }
+            ConnectionState::WaitReconnect(attempt) => {
+                let delay = backoff_for_attempt(attempt);
+                info!("[{}] Reconnecting in {:?} (attempt {})", source.name, delay, attempt);
+                tokio::time::sleep(delay).await;
+                ConnectionState::NotConnected(attempt)
+            }
+        };
     }
 }
 
-async fn subscribe_once(endpoint: &str, token: &str, state: Arc<AppState>) -> anyhow::Result<()> {
-    info!("Connecting to GRPC endpoint: {}", endpoint);
-
-    // Создаем клиент
-    let mut client = GeyserGrpcClient::build_from_shared(endpoint.to_string())?
-        .x_token(if token.is_empty() { None } else { Some(token.to_string()) })?
-        .tls_config(ClientTlsConfig::new().with_native_roots())?
-        .connect()
-        .await?;
+/// Строит клиент, отправляет subscription request и возвращает обе половины
+/// bidi-подписки: стрим апдейтов и sink запросов (который нужно держать живым).
+async fn connect_and_subscribe(source: &GrpcSourceConfig, config: &AppConfig) -> anyhow::Result<(RequestSink, UpdatesStream)> {
+    let mut builder = GeyserGrpcClient::build_from_shared(source.endpoint.clone())?
+        .x_token(source.x_token.clone())?;
+    if source.tls {
+        builder = builder.tls_config(ClientTlsConfig::new().with_native_roots())?;
+    }
+    let mut client = builder.connect().await?;
 
-    info!("✅ GRPC channel connected successfully");
+    info!("[{}] ✅ GRPC channel connected successfully", source.name);
 
-    // Создаем filter для транзакций Pump.fun
-    let pump_fun_program_id = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+    // Создаем filter по наблюдаемым программам (Pump.fun по умолчанию)
     let mut transactions_filter = HashMap::new();
     transactions_filter.insert(
-        "pump_fun".to_string(),
+        "watched_programs".to_string(),
         SubscribeRequestFilterTransactions {
-            account_include: vec![pump_fun_program_id.to_string()],
+            account_include: config.program_ids.clone(),
             vote: Some(false),
             failed: Some(false),
             signature: None,
@@ -61,139 +271,356 @@ async fn subscribe_once(endpoint: &str, token: &str, state: Arc<AppState>) -> an
         },
     );
 
-    info!("Subscribing to Pump.fun Create via Yellowstone GRPC...");
+    info!("[{}] Subscribing to Create via Yellowstone GRPC (commitment={:?})...", source.name, config.commitment_level);
 
-    // Получаем stream и subscribe_tx
-    let (mut subscribe_tx, mut updates_stream) = client.subscribe().await?;
+    let (mut subscribe_tx, updates_stream) = client.subscribe().await?;
 
-    // Создаем subscription request
     let request = SubscribeRequest {
         transactions: transactions_filter,
-        commitment: Some(CommitmentLevel::Processed as i32),
+        commitment: Some(config.commitment_level.to_proto() as i32),
         ..Default::default()
     };
 
-    // Отправляем subscription request
     subscribe_tx.send(request).await?;
-    info!("✅ Subscription request sent successfully");
-    info!("✅ Successfully subscribed, listening for Create transactions...");
+    info!("[{}] ✅ Subscription request sent successfully", source.name);
 
-    loop {
-        match updates_stream.next().await {
-            Some(Ok(message)) => {
-                if let Some(update) = message.update_oneof {
-                    if let UpdateOneof::Transaction(tx) = update {
-                        // Парсим Create транзакцию
-                        if let Some(parsed) = parse_create_transaction(&tx) {
-                            info!("🔥 Create detected: mint={} creator={} signature={}",
-                                parsed.mint_address, parsed.creator_address, parsed.signature);
-
-                            // Отправляем через broadcast channel
-                            if state.tx_sender.send(parsed).is_err() {
-                                warn!("No SSE subscribers, dropping Create transaction");
-                            }
-                        }
-                    }
-                }
-            }
-            Some(Err(e)) => {
-                error!("GRPC stream error: {} (code: {:?}, message: {})",
-                    e,
-                    e.code(),
-                    e.message());
-                if let Some(source) = Error::source(&e) {
-                    error!("Source error: {}", source);
-                }
-                return Err(e.into());
-            }
-            None => {
-                error!("GRPC stream closed unexpectedly (server closed connection)");
-                break;
-            }
-        }
-    }
+    let subscribe_tx: RequestSink = Box::pin(subscribe_tx.sink_map_err(anyhow::Error::from));
+    Ok((subscribe_tx, Box::pin(updates_stream)))
+}
 
-    info!("GRPC stream ended, will reconnect with backoff...");
-    Ok(())
+/// Account layout of the Pump.fun `create`/`create_v2` instruction (public IDL order):
+/// [mint, mint_authority, bonding_curve, associated_bonding_curve, global,
+///  mpl_token_metadata, metadata, creator, system_program, ...].
+const CREATE_MINT_ACCOUNT_INDEX: usize = 0;
+const CREATE_BONDING_CURVE_ACCOUNT_INDEX: usize = 2;
+const CREATE_CREATOR_ACCOUNT_INDEX: usize = 7;
+
+#[derive(BorshDeserialize, Debug)]
+struct CreateArgs {
+    name: String,
+    symbol: String,
+    uri: String,
 }
 
-fn parse_create_transaction(tx: &SubscribeUpdateTransaction) -> Option<CreateTransaction> {
-    let pump_fun_program_id = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+#[derive(BorshDeserialize, Debug)]
+struct CreateV2Args {
+    name: String,
+    symbol: String,
+    uri: String,
+    creator: [u8; 32],
+}
 
-    // Проверяем метаданные транзакции
-    let tx_data = tx.transaction.as_ref()?;
-    let meta = tx_data.meta.as_ref()?;
+struct DecodedCreate {
+    name: String,
+    symbol: String,
+    uri: String,
+    is_create_v2: bool,
+    /// Creator pubkey carried in the instruction data itself, when present. This
+    /// is the authoritative creator - it can legitimately differ from whatever
+    /// account sits at [`CREATE_CREATOR_ACCOUNT_INDEX`] (fee-sharing/delegated
+    /// creation), so callers should prefer it over the account-derived value.
+    creator: Option<[u8; 32]>,
+}
 
-    // Проверяем логи на наличие Pump.fun и Create
-    let log_messages = meta.log_messages.as_ref()?;
-    let log_str = match std::str::from_utf8(log_messages) {
-        Ok(s) => s,
-        Err(_) => return None,
-    };
+/// Anchor discriminators are `sha256("global:<ix_name>")[..8]` - computing them
+/// instead of hardcoding avoids drift if Pump.fun ever renames the instruction.
+fn anchor_discriminator(ix_name: &str) -> [u8; 8] {
+    use sha2::{Digest, Sha256};
 
-    let has_pump_fun = log_str.contains(pump_fun_program_id);
-    let is_create = log_str.contains("Instruction: Create") && !log_str.contains("CreateV2");
-    let is_create_v2 = log_str.contains("Instruction: CreateV2");
+    let hash = Sha256::digest(format!("global:{ix_name}").as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
 
-    if !has_pump_fun || (!is_create && !is_create_v2) {
+/// `create`/`create_v2` discriminators hashed once and cached: `decode_create_instruction`
+/// runs on every matching transaction in the hot path, no reason to re-hash per message.
+fn create_discriminator() -> [u8; 8] {
+    static CACHE: std::sync::OnceLock<[u8; 8]> = std::sync::OnceLock::new();
+    *CACHE.get_or_init(|| anchor_discriminator("create"))
+}
+
+fn create_v2_discriminator() -> [u8; 8] {
+    static CACHE: std::sync::OnceLock<[u8; 8]> = std::sync::OnceLock::new();
+    *CACHE.get_or_init(|| anchor_discriminator("create_v2"))
+}
+
+/// Same relaxation `solana_program::borsh::try_from_slice_unchecked` applies:
+/// deserializes the prefix and returns whatever bytes are left unread, instead of
+/// the strict `try_from_slice` which errors unless the whole slice is consumed.
+fn try_from_slice_unchecked<T: BorshDeserialize>(data: &[u8]) -> std::io::Result<(T, &[u8])> {
+    let mut data_mut = data;
+    let value = T::deserialize(&mut data_mut)?;
+    Ok((value, data_mut))
+}
+
+/// Live `create` transactions append a 32-byte creator pubkey after `uri`, on top
+/// of the three fields `CreateArgs` knows about - this reads it out of the bytes
+/// `try_from_slice_unchecked` left unconsumed, if the trailing field is present.
+fn trailing_creator(rest: &[u8]) -> Option<[u8; 32]> {
+    let bytes: [u8; 32] = rest.get(..32)?.try_into().ok()?;
+    Some(bytes)
+}
+
+fn decode_create_instruction(data: &[u8]) -> Option<DecodedCreate> {
+    if data.len() < 8 {
         return None;
     }
+    let (discriminator, args) = data.split_at(8);
+
+    // Используем `try_from_slice_unchecked`, а не строгий `try_from_slice`: Pump.fun
+    // дописывает в конец инструкции дополнительные поля (например creator pubkey
+    // после uri у живого `create`), и строгий деserializer на таком хвосте вернет
+    // Err, из-за чего валидный Create молча терялся бы.
+    if discriminator == create_discriminator() {
+        let (args, rest): (CreateArgs, _) = try_from_slice_unchecked(args).ok()?;
+        Some(DecodedCreate {
+            name: args.name,
+            symbol: args.symbol,
+            uri: args.uri,
+            is_create_v2: false,
+            creator: trailing_creator(rest),
+        })
+    } else if discriminator == create_v2_discriminator() {
+        let (args, _rest): (CreateV2Args, _) = try_from_slice_unchecked(args).ok()?;
+        Some(DecodedCreate {
+            name: args.name,
+            symbol: args.symbol,
+            uri: args.uri,
+            is_create_v2: true,
+            creator: Some(args.creator),
+        })
+    } else {
+        None
+    }
+}
+
+/// Resolves a `CompiledInstruction` account index against the full key space of a
+/// v0 transaction: static `account_keys` first, then address-table-looked-up
+/// writable addresses, then address-table-looked-up readonly addresses.
+fn resolve_account_key<'a>(
+    index: u32,
+    static_keys: &'a [Vec<u8>],
+    loaded_writable: &'a [Vec<u8>],
+    loaded_readonly: &'a [Vec<u8>],
+) -> Option<&'a [u8]> {
+    let mut index = index as usize;
+
+    if index < static_keys.len() {
+        return Some(&static_keys[index]);
+    }
+    index -= static_keys.len();
+
+    if index < loaded_writable.len() {
+        return Some(&loaded_writable[index]);
+    }
+    index -= loaded_writable.len();
+
+    loaded_readonly.get(index).map(|k| k.as_slice())
+}
+
+fn parse_create_transaction(tx: &SubscribeUpdateTransaction, program_ids: &[String]) -> Option<CreateTransaction> {
+    let tx_data = tx.transaction.as_ref()?;
+    let meta = tx_data.meta.as_ref()?;
+    let tx_transaction = tx_data.transaction.as_ref()?;
+    let message = tx_transaction.message.as_ref()?;
+
+    let static_keys = &message.account_keys;
+    let loaded_writable = &meta.loaded_writable_addresses;
+    let loaded_readonly = &meta.loaded_readonly_addresses;
 
     // Получаем подпись
     let signature = if !tx_data.signature.is_empty() {
         bs58::encode(&tx_data.signature).into_string()
-    } else if let Some(tx_transaction) = tx_data.transaction.as_ref() {
-        if let Some(first_sig) = tx_transaction.signatures.first() {
-            bs58::encode(first_sig).into_string()
-        } else {
-            return None;
-        }
     } else {
-        return None;
+        let first_sig = tx_transaction.signatures.first()?;
+        bs58::encode(first_sig).into_string()
     };
 
-    // Получаем creator (первый аккаунт)
-    let creator_address = if let Some(tx_transaction) = tx_data.transaction.as_ref() {
-        if let Some(message) = tx_transaction.message.as_ref() {
-            if let Some(first_key) = message.account_keys.first() {
-                bs58::encode(first_key).into_string()
-            } else {
-                return None;
-            }
-        } else {
-            return None;
+    // Находим инструкцию, которая реально принадлежит одной из наблюдаемых программ,
+    // вместо того чтобы верить логам: резолвим program_id_index через статические + ATL-ключи.
+    for instruction in &message.instructions {
+        let Some(program_id) = resolve_account_key(
+            instruction.program_id_index,
+            static_keys,
+            loaded_writable,
+            loaded_readonly,
+        ) else {
+            // Индекс не резолвится для этой инструкции - пропускаем только её,
+            // не всю транзакцию, иначе более поздняя инструкция Pump.fun никогда
+            // бы не была замечена.
+            continue;
+        };
+        let program_id_str = bs58::encode(program_id).into_string();
+
+        if !program_ids.iter().any(|id| id == &program_id_str) {
+            continue;
         }
-    } else {
-        return None;
-    };
 
-    // Получаем mint из post_token_balances
-    let post_balances = &meta.post_token_balances;
-    let pre_balances = &meta.pre_token_balances;
+        let decoded = match decode_create_instruction(&instruction.data) {
+            Some(decoded) => decoded,
+            None => continue,
+        };
+
+        let accounts = &instruction.accounts;
+        let mint = accounts.get(CREATE_MINT_ACCOUNT_INDEX)
+            .and_then(|idx| resolve_account_key(*idx as u32, static_keys, loaded_writable, loaded_readonly))?;
+        let bonding_curve = accounts.get(CREATE_BONDING_CURVE_ACCOUNT_INDEX)
+            .and_then(|idx| resolve_account_key(*idx as u32, static_keys, loaded_writable, loaded_readonly))?;
+
+        // Предпочитаем creator из данных инструкции - он может законно отличаться
+        // от аккаунта по фиксированному индексу (fee-sharing/delegated creation) -
+        // и откатываемся на индекс аккаунта только если хвостовое поле отсутствует.
+        let creator_address = match decoded.creator {
+            Some(creator) => bs58::encode(creator).into_string(),
+            None => {
+                let creator = accounts.get(CREATE_CREATOR_ACCOUNT_INDEX)
+                    .and_then(|idx| resolve_account_key(*idx as u32, static_keys, loaded_writable, loaded_readonly))?;
+                bs58::encode(creator).into_string()
+            }
+        };
+
+        return Some(CreateTransaction {
+            signature,
+            mint_address: bs58::encode(mint).into_string(),
+            creator_address,
+            bonding_curve_address: bs58::encode(bonding_curve).into_string(),
+            name: decoded.name,
+            symbol: decoded.symbol,
+            uri: decoded.uri,
+            slot: tx_data.slot,
+            is_create_v2: decoded.is_create_v2,
+        });
+    }
+
+    None
+}
 
-    let pre_mints: std::collections::HashSet<String> = pre_balances.iter()
-        .map(|b| b.mint.clone())
-        .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seen_cache_rejects_repeat_signature() {
+        let mut cache = SeenCache::new(10);
+        assert!(cache.insert_if_new("sig-a"));
+        assert!(!cache.insert_if_new("sig-a"));
+        assert!(cache.insert_if_new("sig-b"));
+    }
+
+    #[test]
+    fn seen_cache_evicts_oldest_past_capacity() {
+        let mut cache = SeenCache::new(2);
+        assert!(cache.insert_if_new("sig-1"));
+        assert!(cache.insert_if_new("sig-2"));
+        assert!(cache.insert_if_new("sig-3"));
+
+        // Ещё живые "sig-2"/"sig-3" остаются дубликатами (повторная проверка кэш не меняет)...
+        assert!(!cache.insert_if_new("sig-2"));
+        assert!(!cache.insert_if_new("sig-3"));
+        // ...а "sig-1" был вытеснен первым, поэтому снова выглядит новым.
+        assert!(cache.insert_if_new("sig-1"));
+    }
+
+    #[test]
+    fn backoff_doubles_then_caps_at_30s() {
+        assert_eq!(backoff_for_attempt(0), Duration::from_secs(1));
+        assert_eq!(backoff_for_attempt(1), Duration::from_secs(2));
+        assert_eq!(backoff_for_attempt(4), Duration::from_secs(16));
+        assert_eq!(backoff_for_attempt(5), Duration::from_secs(30));
+        assert_eq!(backoff_for_attempt(20), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn reset_attempt_resets_only_on_first_message_of_a_session() {
+        // Первое сообщение после Connecting (has_reset=false) сбрасывает counter в 0...
+        assert_eq!(reset_attempt(3, false), (0, true));
+        // ...а последующие сообщения той же Ready-сессии (has_reset=true) его не трогают.
+        assert_eq!(reset_attempt(0, true), (0, true));
+        assert_eq!(reset_attempt(5, true), (5, true));
+    }
 
-    let mut candidate_mints = vec![];
-    for balance in post_balances {
-        let mint = &balance.mint;
-        if !pre_mints.contains(mint) && !mint.contains("11111111111111111111111111111111") {
-            candidate_mints.push(mint.clone());
+    #[test]
+    fn reset_attempt_then_reconnect_backs_off_from_the_reset_value() {
+        // attempt=7 после нескольких неудачных переподключений, затем стрим наконец
+        // дошел до Ready и отдал первое сообщение - счетчик должен сброситься в 0.
+        let (attempt, has_reset) = reset_attempt(7, false);
+        assert_eq!((attempt, has_reset), (0, true));
+
+        // Если сессия потом обрывается ошибкой, WaitReconnect должен взять attempt+1
+        // от *сброшенного* значения (1), а не от значения до подключения (8).
+        assert_eq!(attempt + 1, 1);
+    }
+
+    /// Borsh-кодирует строку как сделал бы `BorshSerialize`: 4-байтная LE длина + utf8.
+    fn borsh_string(s: &str) -> Vec<u8> {
+        let mut out = (s.len() as u32).to_le_bytes().to_vec();
+        out.extend_from_slice(s.as_bytes());
+        out
+    }
+
+    fn encode_create_args(name: &str, symbol: &str, uri: &str, trailing_creator: Option<[u8; 32]>) -> Vec<u8> {
+        let mut data = anchor_discriminator("create").to_vec();
+        data.extend(borsh_string(name));
+        data.extend(borsh_string(symbol));
+        data.extend(borsh_string(uri));
+        if let Some(creator) = trailing_creator {
+            data.extend_from_slice(&creator);
         }
+        data
     }
 
-    let mint_address = candidate_mints.iter()
-        .find(|m: &&String| m.ends_with("pump"))
-        .or_else(|| candidate_mints.first())
-        .cloned()?;
+    #[test]
+    fn decode_create_instruction_picks_up_trailing_creator() {
+        let creator = [7u8; 32];
+        let data = encode_create_args("Name", "SYM", "uri", Some(creator));
 
-    Some(CreateTransaction {
-        signature,
-        mint_address,
-        creator_address,
-        slot: tx_data.slot,
-        is_create_v2,
-    })
-}
+        let decoded = decode_create_instruction(&data).expect("should decode");
+        assert_eq!(decoded.name, "Name");
+        assert_eq!(decoded.symbol, "SYM");
+        assert_eq!(decoded.uri, "uri");
+        assert!(!decoded.is_create_v2);
+        assert_eq!(decoded.creator, Some(creator));
+    }
+
+    #[test]
+    fn decode_create_instruction_without_trailing_bytes_leaves_creator_none() {
+        let data = encode_create_args("Name", "SYM", "uri", None);
+
+        let decoded = decode_create_instruction(&data).expect("should decode");
+        assert_eq!(decoded.creator, None);
+    }
+
+    #[test]
+    fn decode_create_v2_instruction_reads_creator_field() {
+        let creator = [9u8; 32];
+        let mut data = anchor_discriminator("create_v2").to_vec();
+        data.extend(borsh_string("Name"));
+        data.extend(borsh_string("SYM"));
+        data.extend(borsh_string("uri"));
+        data.extend_from_slice(&creator);
 
+        let decoded = decode_create_instruction(&data).expect("should decode");
+        assert!(decoded.is_create_v2);
+        assert_eq!(decoded.creator, Some(creator));
+    }
+
+    #[test]
+    fn decode_create_instruction_rejects_unknown_discriminator() {
+        let data = vec![0u8; 16];
+        assert!(decode_create_instruction(&data).is_none());
+    }
+
+    #[test]
+    fn resolve_account_key_walks_static_then_writable_then_readonly() {
+        let static_keys = vec![vec![1u8], vec![2u8]];
+        let writable = vec![vec![3u8]];
+        let readonly = vec![vec![4u8]];
+
+        assert_eq!(resolve_account_key(0, &static_keys, &writable, &readonly), Some(&[1u8][..]));
+        assert_eq!(resolve_account_key(1, &static_keys, &writable, &readonly), Some(&[2u8][..]));
+        assert_eq!(resolve_account_key(2, &static_keys, &writable, &readonly), Some(&[3u8][..]));
+        assert_eq!(resolve_account_key(3, &static_keys, &writable, &readonly), Some(&[4u8][..]));
+        assert_eq!(resolve_account_key(4, &static_keys, &writable, &readonly), None);
+    }
+}
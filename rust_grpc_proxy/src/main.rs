@@ -1,7 +1,10 @@
 // Rust GRPC Proxy - заменяет grpc_proxy.js
 // Подключается к GRPC напрямую и отправляет Create транзакции через TCP socket (максимальная скорость!)
 
+mod config;
+mod filters;
 mod grpc_client;
+mod metrics;
 
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -13,12 +16,18 @@ use futures::StreamExt;
 use anyhow::Context;
 use bincode;
 
-// Структура Create транзакции (совместима с grpc_proxy.js)
+// Структура Create транзакции, сериализуется в bincode для TCP клиентов. Поля
+// расширились за пределы исходного grpc_proxy.js (bonding_curve_address, name,
+// symbol, uri) - wire-формат больше не совпадает с тем JS-прототипом 1:1.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateTransaction {
     pub signature: String,
     pub mint_address: String,
     pub creator_address: String,
+    pub bonding_curve_address: String,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
     pub slot: u64,
     pub is_create_v2: bool,
 }
@@ -27,6 +36,7 @@ pub struct CreateTransaction {
 #[derive(Clone)]
 pub struct AppState {
     pub tx_sender: broadcast::Sender<CreateTransaction>,
+    pub config: Arc<config::AppConfig>,
 }
 
 #[tokio::main]
@@ -50,10 +60,13 @@ async fn main() -> anyhow::Result<()> {
 
     info!("🚀 Rust GRPC Proxy starting (MAX SPEED MODE - TCP socket)...");
 
+    let config = Arc::new(config::load().context("Failed to load proxy configuration")?);
+
     // Создаем broadcast channel для TCP клиентов
-    let (tx, _) = broadcast::channel::<CreateTransaction>(10000);
+    let (tx, _) = broadcast::channel::<CreateTransaction>(config.broadcast_capacity);
     let state = Arc::new(AppState {
         tx_sender: tx,
+        config: config.clone(),
     });
 
     // Запускаем GRPC подписку
@@ -64,14 +77,20 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
+    // Запускаем Prometheus metrics сервер на отдельном порту
+    tokio::spawn(async move {
+        if let Err(e) = metrics::serve_metrics("0.0.0.0:9090").await {
+            error!("Metrics server failed: {}", e);
+        }
+    });
+
     // Запускаем TCP сервер для максимальной скорости
-    let tcp_listener = TcpListener::bind("0.0.0.0:8725")
+    let tcp_listener = TcpListener::bind(&config.tcp_bind_addr)
         .await
-        .context("Failed to bind TCP socket to port 8725")?;
+        .with_context(|| format!("Failed to bind TCP socket to {}", config.tcp_bind_addr))?;
 
-    info!("🚀 Rust GRPC Proxy TCP server started on port 8725");
+    info!("🚀 Rust GRPC Proxy TCP server started on {}", config.tcp_bind_addr);
     info!("⚡ MAX SPEED: Direct TCP socket (no HTTP overhead)");
-    info!("📡 TCP endpoint: localhost:8725");
 
     // Принимаем TCP подключения
     loop {
@@ -98,14 +117,38 @@ async fn handle_tcp_client(
     state: Arc<AppState>,
 ) -> anyhow::Result<()> {
     use tokio_stream::wrappers::BroadcastStream;
-    
+
+    // Подписываемся на broadcast канал до чтения хендшейка, а не после: иначе
+    // Create, вышедший во время HANDSHAKE_TIMEOUT, был бы просто пропущен, а не
+    // буферизован - особенно больно бьет по старым клиентам, которые вообще не
+    // шлют хендшейк и всегда ждут полный таймаут.
     let rx = state.tx_sender.subscribe();
     let mut broadcast_stream = BroadcastStream::new(rx);
-    
+
+    // Перед стримом читаем опциональный фильтр подписки от клиента
+    let filter = crate::filters::read_subscription_filter(&mut stream).await;
+
+    crate::metrics::LIVE_TCP_SUBSCRIBERS.inc();
+    let result = handle_tcp_client_inner(&mut stream, &mut broadcast_stream, &filter).await;
+    crate::metrics::LIVE_TCP_SUBSCRIBERS.dec();
+
+    info!("TCP client disconnected");
+    result
+}
+
+async fn handle_tcp_client_inner(
+    stream: &mut tokio::net::TcpStream,
+    broadcast_stream: &mut tokio_stream::wrappers::BroadcastStream<CreateTransaction>,
+    filter: &crate::filters::SubscriptionFilter,
+) -> anyhow::Result<()> {
     // Отправляем Create транзакции через TCP с бинарной сериализацией (bincode)
     while let Some(result) = broadcast_stream.next().await {
         match result {
             Ok(create_tx) => {
+                if !filter.matches(&create_tx) {
+                    continue;
+                }
+
                 // Сериализуем в бинарный формат (быстрее чем JSON)
                 match bincode::serialize(&create_tx) {
                     Ok(data) => {
@@ -138,8 +181,7 @@ async fn handle_tcp_client(
             }
         }
     }
-    
-    info!("TCP client disconnected");
+
     Ok(())
 }
 
@@ -0,0 +1,96 @@
+// Prometheus метрики здоровья GRPC-стрима и TCP-подписчиков
+
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram, register_int_counter, register_int_counter_vec, register_int_gauge,
+    Encoder, Histogram, IntCounter, IntCounterVec, IntGauge, TextEncoder,
+};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+lazy_static! {
+    /// Всего распарсенных Create/CreateV2 транзакций (после дедупликации).
+    pub static ref CREATE_TRANSACTIONS_TOTAL: IntCounter = register_int_counter!(
+        "create_transactions_total",
+        "Total number of parsed Create transactions after deduplication, including those dropped for lack of subscribers"
+    )
+    .unwrap();
+
+    /// Сколько Create транзакций выброшено, потому что у broadcast-канала не было получателей.
+    pub static ref CREATE_TRANSACTIONS_DROPPED_NO_SUBSCRIBERS: IntCounter = register_int_counter!(
+        "create_transactions_dropped_no_subscribers_total",
+        "Total number of Create transactions dropped because there were no TCP subscribers"
+    )
+    .unwrap();
+
+    /// Текущее число живых TCP подписчиков.
+    pub static ref LIVE_TCP_SUBSCRIBERS: IntGauge = register_int_gauge!(
+        "live_tcp_subscribers",
+        "Current number of connected TCP subscribers"
+    )
+    .unwrap();
+
+    /// Сколько раз каждый источник переподключался.
+    pub static ref GRPC_RECONNECTS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "grpc_reconnects_total",
+        "Total number of reconnect attempts per Geyser source",
+        &["source"]
+    )
+    .unwrap();
+
+    /// Ошибки GRPC-стрима по источнику и tonic::Code.
+    pub static ref GRPC_STREAM_ERRORS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "grpc_stream_errors_total",
+        "Total number of GRPC stream errors per source and tonic status code",
+        &["source", "code"]
+    )
+    .unwrap();
+
+    /// Задержка между наблюдением транзакции в GRPC-стриме и её рассылкой в broadcast канал.
+    pub static ref END_TO_END_LAG_SECONDS: Histogram = register_histogram!(
+        "end_to_end_lag_seconds",
+        "Latency between observing a transaction in the GRPC stream and broadcasting it"
+    )
+    .unwrap();
+}
+
+/// Поднимает отдельный HTTP сервер, отдающий метрики в формате Prometheus text exposition.
+pub async fn serve_metrics(bind_addr: &str) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(bind_addr).await?;
+    info!("📊 Prometheus metrics exposed on http://{}/metrics", bind_addr);
+
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            // Читаем и игнорируем запрос - отдаем метрики независимо от пути/метода.
+            let mut buf = [0u8; 1024];
+            if let Err(e) = stream.try_read(&mut buf) {
+                if e.kind() != std::io::ErrorKind::WouldBlock {
+                    error!("Failed to read metrics request: {}", e);
+                    return;
+                }
+            }
+
+            let metric_families = prometheus::gather();
+            let mut body = Vec::new();
+            if let Err(e) = TextEncoder::new().encode(&metric_families, &mut body) {
+                error!("Failed to encode metrics: {}", e);
+                return;
+            }
+
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+
+            if let Err(e) = stream.write_all(header.as_bytes()).await {
+                error!("Failed to write metrics response header: {}", e);
+                return;
+            }
+            if let Err(e) = stream.write_all(&body).await {
+                error!("Failed to write metrics response body: {}", e);
+            }
+        });
+    }
+}